@@ -0,0 +1,90 @@
+//! Finds Minecraft worlds that are very likely exact copies of each other
+//! (e.g. the same world backed up into `.minecraft`, a server folder, and a
+//! manual backup), so users can prune redundant copies.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use crate::MinecraftWorld;
+use crate::nbt::{NBTFile, NbtTag};
+
+/// A cluster of worlds sharing a `RandomSeed` and a matching content hash.
+pub(crate) struct DuplicateGroup {
+    pub(crate) seed: i64,
+    pub(crate) members: Vec<PathBuf>,
+    pub(crate) reclaimable_bytes: u64,
+}
+
+/// Clusters `worlds` first by identical `RandomSeed`, then confirms true
+/// copies within each seed-group by hashing a stable subset of their contents
+/// (the world's spawn point and name, plus the sorted sizes of their region
+/// files), so that near-identical-but-diverged worlds sharing a seed aren't
+/// falsely merged. Worlds without a `RandomSeed`, or that are the only world
+/// with their seed, never form a group.
+pub(crate) fn find_duplicate_groups(worlds: &[MinecraftWorld]) -> Vec<DuplicateGroup> {
+    let mut by_seed: HashMap<i64, Vec<&MinecraftWorld>> = HashMap::new();
+    for world in worlds {
+        if let Some(seed) = world.level_dat.random_seed {
+            by_seed.entry(seed).or_default().push(world);
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (seed, candidates) in by_seed {
+        if candidates.len() < 2 {
+            continue;
+        }
+        let mut by_content_hash: HashMap<u64, Vec<&MinecraftWorld>> = HashMap::new();
+        for world in candidates {
+            by_content_hash.entry(content_hash(&world.path)).or_default().push(world);
+        }
+        for members in by_content_hash.into_values() {
+            if members.len() < 2 {
+                continue;
+            }
+            // Reclaimable size = the combined size of every copy but the one we'd keep:
+            let total_size: u64 = members.iter().map(|w| w.size_in_bytes).sum();
+            let largest_copy = members.iter().map(|w| w.size_in_bytes).max().unwrap_or(0);
+            groups.push(DuplicateGroup {
+                seed,
+                members: members.into_iter().map(|w| w.path.clone()).collect(),
+                reclaimable_bytes: total_size.saturating_sub(largest_copy),
+            });
+        }
+    }
+    groups
+}
+
+/// Hashes the world's stable identifying fields (its name and spawn point)
+/// plus the sorted sizes of every file under `world_path/region/`, as a cheap
+/// stand-in for "is this really the same world". Deliberately avoids hashing
+/// the raw `level.dat` bytes: that file also carries volatile per-session
+/// fields (`LastPlayed`, `Time`, `DayTime`) that change the moment a backup
+/// is ever reopened, which would otherwise break the match against its
+/// source world.
+fn content_hash(world_path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    if let Ok(level_dat) = NBTFile::new(&world_path.join("level.dat")) {
+        if let Some(NbtTag::String(level_name)) = level_dat.get(&["Data", "LevelName"]) {
+            level_name.hash(&mut hasher);
+        }
+        for spawn_coord in ["SpawnX", "SpawnY", "SpawnZ"] {
+            if let Some(NbtTag::Int(v)) = level_dat.get(&["Data", spawn_coord]) {
+                v.hash(&mut hasher);
+            }
+        }
+    }
+    let mut region_file_sizes: Vec<u64> = fs::read_dir(world_path.join("region"))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .collect();
+    region_file_sizes.sort_unstable();
+    region_file_sizes.hash(&mut hasher);
+    hasher.finish()
+}
@@ -1,20 +1,25 @@
-use std::{env, fs};
-use std::collections::HashMap;
+mod nbt;
+mod mojang;
+mod dupes;
+
+use std::fs;
+use std::collections::HashSet;
 use std::fmt::Display;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 use phf::{phf_map};
-use std::fs::File;
-use std::io::BufReader;
-use flate2::read::GzDecoder;
-use std::io::Read;
 use std::sync::Mutex;
 use chrono::prelude::DateTime;
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 use std::time::{UNIX_EPOCH, Duration};
-use reqwest::blocking::Client;
-use serde::Deserialize;
-use std::{thread, time};
+use std::thread;
+use rayon::prelude::*;
+use clap::Parser;
+use serde::Serialize;
+use nbt::NBTFile;
+use mojang::UsernameResolver;
+use dupes::find_duplicate_groups;
 
 /// An integer indicating the Minecraft version.
 /// Cf. https://minecraft.wiki/w/Data_version
@@ -117,88 +122,137 @@ const MINECRAFT_PATH: &'static str = "~/Library/Application Support/minecraft";
 #[cfg(target_os = "linux")]
 const MINECRAFT_PATH: &'static str = "~/.minecraft";
 
-fn unix_to_str(unix_timestamp_in_ms: i64) -> String {
-    let system_time = UNIX_EPOCH + Duration::from_millis(unix_timestamp_in_ms as u64);
-    let date_time = DateTime::<Utc>::from(system_time);
-    date_time.format("%Y-%m-%d %H:%M:%S").to_string()
+/// Command line arguments, cf. <https://docs.rs/clap>.
+#[derive(Parser)]
+#[command(author, version, about = "Scans the filesystem for Minecraft world saves and reports information about them.")]
+struct Cli {
+    /// Folders to search for Minecraft worlds in.
+    /// Defaults to the platform's `.minecraft` folder, the home directory (Unix only), and the filesystem root.
+    roots: Vec<String>,
+
+    /// Only show worlds whose "DataVersion" is >= this value.
+    #[arg(long)]
+    min_version: Option<i32>,
+
+    /// Only show worlds whose "DataVersion" is <= this value.
+    #[arg(long)]
+    max_version: Option<i32>,
+
+    /// Only show worlds whose "RandomSeed" matches this value.
+    #[arg(long, allow_hyphen_values = true)]
+    seed: Option<i64>,
+
+    /// Only show worlds last played on or after this date (format: YYYY-MM-DD).
+    #[arg(long, value_parser = parse_played_since_date)]
+    played_since: Option<chrono::NaiveDate>,
+
+    /// How to sort the worlds before printing them.
+    #[arg(long, value_enum)]
+    sort_by: Option<SortBy>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Caps the number of worker threads used to parse worlds in parallel (defaults to the number of CPUs).
+    #[arg(long)]
+    jobs: Option<usize>,
+
+    /// Skip resolving player UUIDs to usernames via the Mojang API entirely.
+    #[arg(long)]
+    no_mojang_api: bool,
+
+    /// Timeout, in seconds, for Mojang API requests.
+    #[arg(long, default_value_t = 3)]
+    mojang_api_timeout: u64,
+
+    /// Instead of printing world details, group worlds that are very likely
+    /// exact copies of each other (same seed, same world name and spawn point,
+    /// same region file sizes) and report each group's member paths and reclaimable size.
+    #[arg(long)]
+    find_duplicates: bool,
 }
 
-lazy_static::lazy_static! {
-    static ref USERNAME_CACHE: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum SortBy {
+    Size,
+    LastPlayed,
+    Playtime,
+    Version,
 }
 
-#[derive(Deserialize)]
-struct MinecraftProfile {
-    id: String,
-    name: String,
+#[derive(Clone, Copy, PartialEq, clap::ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
-// ...as returned by the https://api.minecraftservices.com/minecraft/profile/lookup/<UUID> API!
-
-fn uuid_to_uname(uuid: &str) -> Result<String, String> {  // ToDo: allow user to disable this feature using --no-mojang-api and to alter the timeout using --mojang-api-timeout
-    // https://minecraft.wiki/w/Mojang_API#Query_player's_username
-    //   API: https://api.minecraftservices.com/minecraft/profile/lookup/<UUID>
-    // where <UUID> must be without the minuses ("-")!
-    //
-    // Example GET request:
-    //   https://api.minecraftservices.com/minecraft/profile/lookup/afe703c40a8f4b448301974a3305820d
-    //
-    // Example JSON response:
-    //   {
-    //     "id" : "afe703c40a8f4b448301974a3305820d",
-    //     "name" : "horstder2te"
-    //   }
-
-    // Remove dashes from the UUID:
-    let uuid_no_dashes: String = uuid.replace("-", "");
-
-    // Check the cache first:
-    {
-        let cache = USERNAME_CACHE.lock().unwrap();
-        if let Some(username) = cache.get(&uuid_no_dashes) {
-            return Ok(username.clone());
-        }
-    }
-
-    // Construct the API URL:
-    let url = format!("https://api.minecraftservices.com/minecraft/profile/lookup/{}", uuid_no_dashes);
-
-    // Create a blocking HTTP client with a timeout:
-    let client = Client::builder()
-        .timeout(Duration::from_secs(3)) // Set timeout to 3 seconds
-        .build()
-        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
 
-    // Send the GET request:
-    let response = client.get(&url).send()
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
+/// clap `value_parser` for `--played-since`: rejects a malformed date with a
+/// normal clap usage error instead of letting it through as a `String` and
+/// panicking later, after the (potentially minutes-long) filesystem scan.
+fn parse_played_since_date(date_str: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+        .map_err(|e| format!("invalid date {:?} (expected YYYY-MM-DD): {}", date_str, e))
+}
 
-    // Sleep after each GET request to avoid an "HTTP 429 Too Many Requests":
-    thread::sleep(Duration::from_millis(1000));
+fn unix_to_str(unix_timestamp_in_ms: i64) -> String {
+    let system_time = UNIX_EPOCH + Duration::from_millis(unix_timestamp_in_ms as u64);
+    let date_time = DateTime::<Utc>::from(system_time);
+    date_time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
 
-    // Check for successful response:
-    if response.status().is_success() {
-        // Parse the JSON response:
-        let profile: MinecraftProfile = response.json()
-            .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
+/// Formats a byte count human-readably as `B`/`KiB`/`MiB`/`GiB`, e.g. `12.34 GiB`.
+fn format_byte_size(bytes: u64, precision: usize) -> String {
+    const KIB: f64 = 1024.0;
+    const MIB: f64 = KIB * 1024.0;
+    const GIB: f64 = MIB * 1024.0;
+    let bytes_f = bytes as f64;
+    if bytes_f >= GIB {
+        format!("{:.precision$} GiB", bytes_f / GIB, precision = precision)
+    } else if bytes_f >= MIB {
+        format!("{:.precision$} MiB", bytes_f / MIB, precision = precision)
+    } else if bytes_f >= KIB {
+        format!("{:.precision$} KiB", bytes_f / KIB, precision = precision)
+    } else {
+        format!("{} B", bytes)
+    }
+}
 
-        // Update the cache:
-        {
-            let mut cache = USERNAME_CACHE.lock().unwrap();
-            cache.insert(uuid_no_dashes.clone(), profile.name.clone());
+/// Recursively sums the byte size of every file under `path` (the whole world
+/// folder, region files included) and finds the newest modification time among
+/// them, returned as Unix milliseconds so it can be fed into [`unix_to_str`].
+fn world_size_and_last_modified(path: &Path) -> (u64, i64) {
+    let mut total_size: u64 = 0;
+    let mut newest_modified: i64 = 0;
+    for entry in WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        if let Ok(metadata) = entry.metadata() {
+            total_size += metadata.len();
+            if let Ok(modified) = metadata.modified() {
+                if let Ok(since_epoch) = modified.duration_since(UNIX_EPOCH) {
+                    let millis = since_epoch.as_millis() as i64;
+                    if millis > newest_modified {
+                        newest_modified = millis;
+                    }
+                }
+            }
         }
-
-        // Return the username:
-        Ok(profile.name)
-    } else {
-        Err(format!("Failed to fetch username for UUID {}: HTTP {}", uuid, response.status()))
     }
+    (total_size, newest_modified)
 }
 
+
 /// A Minecraft world is a folder that must at least contain a valid "level.dat" file.
-struct MinecraftWorld {
-    path: PathBuf,
-    level_dat: LevelDat,  // /world/level.dat file
+#[derive(Serialize)]
+pub(crate) struct MinecraftWorld {
+    pub(crate) path: PathBuf,
+    pub(crate) level_dat: LevelDat,  // /world/level.dat file
     player_dat: Vec<PlayerDat>, // /world/playerdata/*.dat files
+    pub(crate) size_in_bytes: u64, // combined size of every file under `path`
+    last_modified: i64, // newest mtime among every file under `path`, Unix ms
 }
 
 impl Display for MinecraftWorld {
@@ -215,15 +269,15 @@ impl Display for MinecraftWorld {
         }
         f.write_str(&format!("Seed: {}\n", self.level_dat.random_seed.map(|s| s.to_string()).unwrap_or("???".to_string())))?;
         f.write_str(&format!("Last played: {} (UNIX: {})\n", unix_to_str(self.level_dat.last_played), self.level_dat.last_played))?;
-        f.write_str(&format!("Modified: {}\n", "todo"))?; // TODO
-        f.write_str(&format!("Size: {}\n", "todo"))?; // TODO
+        f.write_str(&format!("Modified: {} (UNIX: {})\n", unix_to_str(self.last_modified), self.last_modified))?;
+        f.write_str(&format!("Size: {}\n", format_byte_size(self.size_in_bytes, 2)))?;
         f.write_str(&format!("Ticks passed: {} (~{:.2} hours)\n", self.level_dat.time, (self.level_dat.time as f64)/(20.0*3600.0)))?; // TODO: remove?!
         f.write_str(&format!("In-game days passed: {}\n", self.level_dat.day_time as f64 / 24000.0))?;
         f.write_str(&format!("Current time: {} (0 = sunrise, 6000 = midday, 12000 = sunset, 18000 = midnight)\n", self.level_dat.day_time % 24000))?;
         f.write_str(&format!("Difficulty: {} (0 = Peaceful, 1 = Easy, 2 = Normal, 3 = Hard)\n", self.level_dat.difficulty))?;
         f.write_str(&format!("Players: {}\n", self.player_dat.len()))?;
         for player in self.player_dat.iter() {
-            f.write_str(&format!("    - {} ({}) @ x={:.2}, y={:.2}, z={:.2} (Health: {:.2}, Food: {})\n", player.uuid, uuid_to_uname(&player.uuid).unwrap_or("???".to_string()), player.pos.0, player.pos.1, player.pos.2, player.health, player.food_level))?;
+            f.write_str(&format!("    - {} ({}) @ x={:.2}, y={:.2}, z={:.2} (Health: {:.2}, Food: {})\n", player.uuid, player.username.as_deref().unwrap_or("???"), player.pos.0, player.pos.1, player.pos.2, player.health, player.food_level))?;
         }
         Ok(())
     }
@@ -232,11 +286,14 @@ impl Display for MinecraftWorld {
 impl MinecraftWorld {
     fn new(level_dat: &Path) -> Result<Self, NBTError> {
         let parent_dir = level_dat.parent().map(PathBuf::from).ok_or(NBTError { msg: format!("{:?} has no parent", level_dat)})?;
+        let (size_in_bytes, last_modified) = world_size_and_last_modified(&parent_dir);
         Ok(
             Self {
                 path: parent_dir.clone(),
                 level_dat: LevelDat::new(level_dat)?,
                 player_dat: PlayerDat::for_each_dat_file_in(&parent_dir.join("playerdata")),
+                size_in_bytes,
+                last_modified,
             }
         )
     }
@@ -247,12 +304,13 @@ impl MinecraftWorld {
 /// The data is stored in the so called "NBT" format,
 /// cf. https://minecraft.wiki/w/NBT_format
 /// Each Minecraft world folder must contain such a "level.dat" file.
-struct LevelDat {
+#[derive(Serialize)]
+pub(crate) struct LevelDat {
     day_time: i64, // "DayTime": 1 day = 24000, does not(!) reset to zero
     difficulty: i8, // "Difficulty"
     data_version: Option<i32>, // "DataVersion": https://minecraft.wiki/w/Data_version (MC v1.9+)
     last_played: i64, // "LastPlayed": "The Unix time in milliseconds when the level was last loaded."
-    random_seed: Option<i64>, // "RandomSeed": "The random level seed used to generate consistent terrain."
+    pub(crate) random_seed: Option<i64>, // "RandomSeed": "The random level seed used to generate consistent terrain."
     time: i64, // "Time": "The number of ticks since the start of the level."
 }
 
@@ -261,12 +319,12 @@ impl LevelDat {
         let nbt_file: NBTFile = NBTFile::new(level_dat)?;
         Ok(
             Self {
-                day_time: nbt_file.get_long("DayTime")?,
-                difficulty: nbt_file.get_byte("Difficulty")?,
-                data_version: nbt_file.get_int("DataVersion").ok(),
-                last_played: nbt_file.get_long("LastPlayed")?,
-                random_seed: nbt_file.get_long("RandomSeed").ok(),
-                time: nbt_file.get_long("Time")?,
+                day_time: nbt_file.get_long(&["Data", "DayTime"])?,
+                difficulty: nbt_file.get_byte(&["Data", "Difficulty"])?,
+                data_version: nbt_file.get_int(&["Data", "DataVersion"]).ok(),
+                last_played: nbt_file.get_long(&["Data", "LastPlayed"])?,
+                random_seed: nbt_file.get_long(&["Data", "RandomSeed"]).ok(),
+                time: nbt_file.get_long(&["Data", "Time"])?,
             }
         )
     }
@@ -277,8 +335,10 @@ impl LevelDat {
 /// Just like the "level.dat" file, it is also stored in "NBT" format,
 /// cf. https://minecraft.wiki/w/NBT_format
 /// The /world/playerdata/ folder contains a <player>.dat file for each player.
+#[derive(Serialize)]
 struct PlayerDat {
     uuid: String, // extracted from the file name, e.g. "afe703c4-0a8f-4b44-8301-974a3305820d.dat"
+    username: Option<String>, // resolved from `uuid` via the Mojang API, if available
     health: f32, // "Health"
     food_level: i32, // "foodLevel"
     pos: (f64, f64, f64), // "Pos": "List of 3 doubles describing the current X, Y, and Z position (coordinates) of the entity."
@@ -290,9 +350,10 @@ impl PlayerDat {
         Ok(
             Self {
                 uuid: player_dat.file_name().unwrap().to_str().unwrap().strip_suffix(".dat").unwrap().to_string(),
-                health: nbt_file.get_float("Health")?,
-                food_level: nbt_file.get_int("foodLevel")?,
-                pos: nbt_file.get_double_triplet("Pos")?,
+                username: None, // filled in later, once usernames have been resolved in bulk
+                health: nbt_file.get_float(&["Health"])?,
+                food_level: nbt_file.get_int(&["foodLevel"])?,
+                pos: nbt_file.get_double_triplet(&["Pos"])?,
             }
         )
     }
@@ -314,99 +375,23 @@ impl PlayerDat {
 }
 
 #[derive(Debug)]
-struct NBTError {
+pub(crate) struct NBTError {
     msg: String,
 }
 
-/// Cf. https://minecraft.wiki/w/NBT_format
-struct NBTFile {
-    data: Vec<u8>,
-}
-
-impl NBTFile {
-    fn new(path: &Path) -> Result<Self, NBTError> {
-        let file = File::open(path).map_err(|_e| NBTError {msg: format!("Could not open file {:?}", path)})?;
-        let file = BufReader::new(file);
-        let mut file = GzDecoder::new(file);
-        let mut bytes = Vec::new();
-        file.read_to_end(&mut bytes).map_err(|_e| NBTError {msg: format!("Could not read file {:?}", path)})?;
-
-        Ok(
-            Self {
-                data: bytes,
-            }
-        )
-    }
-
-    fn get_byte(&self, name: &str) -> Result<i8, NBTError> {
-        const SIZE: usize = std::mem::size_of::<i8>();
-        for i in 0..=self.data.len()-name.len()-SIZE {
-            if self.data[i..i+name.len()].to_vec() == name.as_bytes() {
-                return Ok(i8::from_be_bytes([
-                    self.data[i+name.len()],
-                ]))
-            }
-        }
-        Err(NBTError {msg: format!("'{}' not found", name)})
-    }
-
-    fn get_int(&self, name: &str) -> Result<i32, NBTError> {
-        const SIZE: usize = std::mem::size_of::<i32>();
-        for i in 0..=self.data.len()-name.len()-SIZE {
-            if self.data[i..i+name.len()].to_vec() == name.as_bytes() {
-                return Ok(i32::from_be_bytes([
-                    self.data[i+name.len()],
-                    self.data[i+name.len()+1],
-                    self.data[i+name.len()+2],
-                    self.data[i+name.len()+3],
-                ]))
-            }
-        }
-        Err(NBTError {msg: format!("'{}' not found", name)})
-    }
-
-    fn get_long(&self, name: &str) -> Result<i64, NBTError> {
-        const SIZE: usize = std::mem::size_of::<i64>();
-        for i in 0..=self.data.len()-name.len()-SIZE {
-            if self.data[i..i+name.len()].to_vec() == name.as_bytes() {
-                return Ok(i64::from_be_bytes([
-                    self.data[i+name.len()],
-                    self.data[i+name.len()+1],
-                    self.data[i+name.len()+2],
-                    self.data[i+name.len()+3],
-                    self.data[i+name.len()+4],
-                    self.data[i+name.len()+5],
-                    self.data[i+name.len()+6],
-                    self.data[i+name.len()+7],
-                ]))
-            }
-        }
-        Err(NBTError {msg: format!("'{}' not found", name)})
-    }
-
-    fn get_float(&self, name: &str) -> Result<f32, NBTError> {
-        const SIZE: usize = std::mem::size_of::<f32>();
-        for i in 0..=self.data.len()-name.len()-SIZE {
-            if self.data[i..i+name.len()].to_vec() == name.as_bytes() {
-                return Ok(f32::from_be_bytes([
-                    self.data[i+name.len()],
-                    self.data[i+name.len()+1],
-                    self.data[i+name.len()+2],
-                    self.data[i+name.len()+3],
-                ]))
-            }
-        }
-        Err(NBTError {msg: format!("'{}' not found", name)})
-    }
-
-    fn get_double_triplet(&self, name: &str) -> Result<(f64, f64, f64), NBTError> {
-        Ok((0.0, 0.0, 0.0))  // TODO
-    }
+/// A progress update sent from a worker thread to the printer thread while
+/// worlds are being parsed in parallel.
+enum ProgressEvent {
+    /// One `level.dat` has just finished being parsed (successfully or not).
+    WorldChecked,
 }
 
 fn main() {
-    // (1.) Parse command line args or use default values:
-    let mut args: Vec<String> = env::args().skip(1).collect();
+    // (1.) Parse command line args, falling back to the platform's `.minecraft`
+    //      folder, the home directory (Unix only), and the filesystem root if no search
+    //      roots were given:
+    let cli = Cli::parse();
+    let mut args: Vec<String> = cli.roots.clone();
     if args.len() == 0 {
         #[cfg(unix)]
         args.push(shellexpand::tilde(MINECRAFT_PATH).to_string());
@@ -422,60 +407,206 @@ fn main() {
         args.push("C:\\".to_string());
     }
 
-    // (2.) Iterate through each given folder and print each MinecraftWorld found, store paths
-    //      of MinecraftWorlds already found to avoid printing them twice when multiple paths
-    //      were given (e.g., first "~/.minecraft" and then "/"):
-    let mut paths: Vec<PathBuf> = Vec::new();
-    let mut min_version: i32 = i32::MAX;
-    let mut max_version: i32 = i32::MIN;
-    for dir in args {
-        println!();
+    if let Some(jobs) = cli.jobs {
+        rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global()
+            .expect("failed to build rayon thread pool (can only be configured once)");
+    }
+
+    // (2.) Walk each given folder (in parallel) collecting every "level.dat" found.
+    //      A shared, mutex-guarded HashSet dedupes worlds reachable through more
+    //      than one search root (e.g. first "~/.minecraft" and then "/") in O(1)
+    //      per insertion, instead of the old O(n) `paths.contains` scan:
+    let found_paths: Mutex<HashSet<PathBuf>> = Mutex::new(HashSet::new());
+    args.par_iter().for_each(|dir| {
         println!("Walking through {} ...", dir);
-        println!();
         for level_dat_file in WalkDir::new(dir)
             .into_iter()
             .filter_map(Result::ok)
             .filter(|e| e.file_type().is_file())
             .filter(|e| e.file_name() == "level.dat")
         {
-
-            // Ignore this level.dat file if it has already been processed before:
-            let path: &Path = level_dat_file.path();
-            let path_buf: PathBuf = path.to_path_buf();
-            if paths.contains(&path_buf) {
-                continue;
-            } else {
-                paths.push(path_buf);
+            found_paths.lock().unwrap().insert(level_dat_file.path().to_path_buf());
+        }
+    });
+    let paths: Vec<PathBuf> = found_paths.into_inner().unwrap().into_iter().collect();
+    let total_worlds = paths.len();
+
+    // (3.) Parse all worlds in parallel with rayon, reporting progress via a
+    //      crossbeam channel consumed by a dedicated printer thread, since each
+    //      world can involve slow disk I/O and we don't want one straggler to
+    //      stall the whole scan with no feedback:
+    println!();
+    println!("Found {} level.dat file(s). Parsing...", total_worlds);
+    println!();
+    let (progress_tx, progress_rx) = crossbeam_channel::unbounded::<ProgressEvent>();
+    let printer = thread::spawn(move || {
+        let mut checked: usize = 0;
+        for ProgressEvent::WorldChecked in progress_rx.iter() {
+            checked += 1;
+            print!("\rParsing worlds: {}/{total_worlds}", checked);
+            let _ = io::stdout().flush();
+        }
+        println!();
+    });
+
+    let mut mc_worlds: Vec<MinecraftWorld> = paths.par_iter()
+        .filter_map(|path| {
+            let result = MinecraftWorld::new(path);
+            let _ = progress_tx.send(ProgressEvent::WorldChecked);
+            match result {
+                Ok(mc_world) => Some(mc_world),
+                Err(err) => {
+                    eprintln!("{:?} is invalid: {:?}", path, err.msg);
+                    None
+                }
+            }
+        })
+        .collect();
+    drop(progress_tx);
+    printer.join().expect("printer thread panicked");
+
+    // (4.) Resolve every distinct player UUID across all worlds in a single
+    //      batched pass (unless disabled), then attach the results to each
+    //      player so that printing below makes no network calls at all:
+    let resolver = UsernameResolver::load(!cli.no_mojang_api, Duration::from_secs(cli.mojang_api_timeout));
+    let all_uuids: Vec<&str> = mc_worlds.iter()
+        .flat_map(|w| w.player_dat.iter().map(|p| p.uuid.as_str()))
+        .collect();
+    resolver.resolve_all(all_uuids);
+    for mc_world in mc_worlds.iter_mut() {
+        for player in mc_world.player_dat.iter_mut() {
+            player.username = resolver.cached_name(&player.uuid);
+        }
+    }
+    resolver.save();
+
+    // (5.) Apply --min-version/--max-version/--seed/--played-since filtering:
+    let played_since_millis: Option<i64> = cli.played_since.map(|date| {
+        Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap()).timestamp_millis()
+    });
+    mc_worlds.retain(|w| {
+        if let Some(min_version) = cli.min_version {
+            if w.level_dat.data_version.is_none_or(|v| v < min_version) {
+                return false;
             }
+        }
+        if let Some(max_version) = cli.max_version {
+            // A missing DataVersion means a pre-1.9 world, which is always
+            // older than any --max-version threshold, so it should pass here
+            // (unlike the min_version check above, where it should fail).
+            if w.level_dat.data_version.is_some_and(|v| v > max_version) {
+                return false;
+            }
+        }
+        if let Some(seed) = cli.seed {
+            if w.level_dat.random_seed != Some(seed) {
+                return false;
+            }
+        }
+        if let Some(threshold) = played_since_millis {
+            if w.level_dat.last_played < threshold {
+                return false;
+            }
+        }
+        true
+    });
+
+    // (6.) Apply --sort-by:
+    if let Some(sort_by) = cli.sort_by {
+        match sort_by {
+            SortBy::Size => mc_worlds.sort_by_key(|w| std::cmp::Reverse(w.size_in_bytes)),
+            SortBy::LastPlayed => mc_worlds.sort_by_key(|w| std::cmp::Reverse(w.level_dat.last_played)),
+            SortBy::Playtime => mc_worlds.sort_by_key(|w| std::cmp::Reverse(w.level_dat.time)),
+            SortBy::Version => mc_worlds.sort_by_key(|w| std::cmp::Reverse(w.level_dat.data_version)),
+        }
+    }
 
-            // Try to parse the level.dat and associated files and print Minecraft world info,
-            //   otherwise print an error message:
-            println!();
-            match MinecraftWorld::new(path) {
-                Ok(mc_world) => {
-                    println!("{}", mc_world);
-                    if let Some(version) = mc_world.level_dat.data_version {
-                        if version < min_version {
-                            min_version = version;
-                        }
-                        if version > max_version {
-                            max_version = version;
-                        }
-                    }
-                }
-                Err(err) => {
-                    println!("{:?} is invalid: {:?}", path, err.msg);
+    // (7.) In --find-duplicates mode, report duplicate world groups instead of
+    //      the usual per-world details and return early:
+    if cli.find_duplicates {
+        let groups = find_duplicate_groups(&mc_worlds);
+        println!();
+        if groups.is_empty() {
+            println!("No duplicate worlds found.");
+        } else {
+            let mut total_reclaimable: u64 = 0;
+            for (i, group) in groups.iter().enumerate() {
+                println!("Duplicate group #{} (seed {}):", i + 1, group.seed);
+                for member in &group.members {
+                    println!("    - {:?}", member);
                 }
+                println!("  Reclaimable size: {}", format_byte_size(group.reclaimable_bytes, 2));
+                println!();
+                total_reclaimable += group.reclaimable_bytes;
             }
-            println!();
+            println!("Found {} duplicate group(s), {} reclaimable in total.", groups.len(), format_byte_size(total_reclaimable, 2));
+        }
+        println!();
+        return;
+    }
+
+    // (8.) Emit the result in the requested --format:
+    if cli.format == OutputFormat::Json {
+        match serde_json::to_string_pretty(&mc_worlds) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Failed to serialize worlds to JSON: {}", e),
+        }
+        return;
+    }
 
+    // Print every matching world as text and accumulate superlatives:
+    let mut min_version: Option<i32> = None;
+    let mut max_version: Option<i32> = None;
+    let mut largest_world: Option<(PathBuf, u64)> = None;
+    let mut oldest_world: Option<(PathBuf, i64)> = None;
+    let mut newest_world: Option<(PathBuf, i64)> = None;
+    let mut longest_played_world: Option<(PathBuf, i64)> = None;
+    for mc_world in mc_worlds.iter() {
+        println!();
+        println!("{}", mc_world);
+        if let Some(version) = mc_world.level_dat.data_version {
+            if min_version.is_none_or(|v| version < v) {
+                min_version = Some(version);
+            }
+            if max_version.is_none_or(|v| version > v) {
+                max_version = Some(version);
+            }
+        }
+        if largest_world.as_ref().is_none_or(|(_, size)| mc_world.size_in_bytes > *size) {
+            largest_world = Some((mc_world.path.clone(), mc_world.size_in_bytes));
+        }
+        if oldest_world.as_ref().is_none_or(|(_, last_played)| mc_world.level_dat.last_played < *last_played) {
+            oldest_world = Some((mc_world.path.clone(), mc_world.level_dat.last_played));
+        }
+        if newest_world.as_ref().is_none_or(|(_, last_played)| mc_world.level_dat.last_played > *last_played) {
+            newest_world = Some((mc_world.path.clone(), mc_world.level_dat.last_played));
+        }
+        if longest_played_world.as_ref().is_none_or(|(_, time)| mc_world.level_dat.time > *time) {
+            longest_played_world = Some((mc_world.path.clone(), mc_world.level_dat.time));
         }
     }
 
-    println!("Done. {} Minecraft worlds were found.", paths.len());
     println!();
-    println!("Highest version encountered: {} ({})", max_version, DATA_VERSIONS.get(&max_version).unwrap_or(&"???"));
-    println!("Lowest >=1.9 version encountered: {} ({})", min_version, DATA_VERSIONS.get(&min_version).unwrap_or(&"???"));
+    println!("Done. {} Minecraft worlds were found, {} matched the given filters.", total_worlds, mc_worlds.len());
+    println!();
+    if let Some(version) = max_version {
+        println!("Highest version encountered: {} ({})", version, DATA_VERSIONS.get(&version).unwrap_or(&"???"));
+    }
+    if let Some(version) = min_version {
+        println!("Lowest >=1.9 version encountered: {} ({})", version, DATA_VERSIONS.get(&version).unwrap_or(&"???"));
+    }
+    println!();
+    if let Some((path, size)) = largest_world {
+        println!("Largest world: {:?} ({})", path, format_byte_size(size, 2));
+    }
+    if let Some((path, last_played)) = oldest_world {
+        println!("Oldest world (by last played): {:?} ({})", path, unix_to_str(last_played));
+    }
+    if let Some((path, last_played)) = newest_world {
+        println!("Newest world (by last played): {:?} ({})", path, unix_to_str(last_played));
+    }
+    if let Some((path, time)) = longest_played_world {
+        println!("Longest played world: {:?} ({} ticks, ~{:.2} hours)", path, time, (time as f64) / (20.0 * 3600.0));
+    }
     println!();
-    // TODO: print out some final statistics (like oldest world, largest world, longest play time, ...)
 }
@@ -0,0 +1,143 @@
+//! Resolves player UUIDs to their current Minecraft usernames via the Mojang API,
+//! cf. https://minecraft.wiki/w/Mojang_API#Query_player's_username
+//!
+//! Lookups are batched (up to 10 UUIDs per request, via the bulk endpoint) and
+//! the results are cached to disk so that repeat scans of the same worlds make
+//! zero network calls for players that have already been resolved.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+
+const CACHE_FILE: &str = "~/.minecraft_world_finder_username_cache.json";
+const BULK_LOOKUP_URL: &str = "https://api.minecraftservices.com/minecraft/profile/lookup/bulk/byuuid";
+const BULK_BATCH_SIZE: usize = 10;
+
+/// As returned by the `.../lookup/bulk/byuuid` API (and by the single-UUID
+/// `.../lookup/<UUID>` one, which has the same shape).
+#[derive(Deserialize)]
+struct MinecraftProfile {
+    id: String,
+    name: String,
+}
+
+/// A single cached UUID -> username mapping, together with when it was fetched.
+#[derive(Serialize, Deserialize, Clone)]
+struct CachedUsername {
+    name: String,
+    fetched_at: i64, // Unix seconds when this entry was fetched from Mojang
+}
+
+/// Resolves and caches UUID -> username mappings for the lifetime of a run.
+pub struct UsernameResolver {
+    cache: Mutex<HashMap<String, CachedUsername>>,
+    cache_path: PathBuf,
+    enabled: bool,
+    timeout: Duration,
+}
+
+impl UsernameResolver {
+    /// Loads the on-disk cache (if any). `enabled` corresponds to `--no-mojang-api`
+    /// (inverted) and `timeout` to `--mojang-api-timeout`.
+    pub fn load(enabled: bool, timeout: Duration) -> Self {
+        let cache_path = PathBuf::from(shellexpand::tilde(CACHE_FILE).to_string());
+        let cache = fs::read_to_string(&cache_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<HashMap<String, CachedUsername>>(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            cache: Mutex::new(cache),
+            cache_path,
+            enabled,
+            timeout,
+        }
+    }
+
+    /// Writes the cache back to disk. Call once before the process exits.
+    pub fn save(&self) {
+        let cache = self.cache.lock().unwrap();
+        match serde_json::to_string_pretty(&*cache) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&self.cache_path, json) {
+                    eprintln!("Failed to write username cache to {:?}: {}", self.cache_path, e);
+                }
+            }
+            Err(e) => eprintln!("Failed to serialize username cache: {}", e),
+        }
+    }
+
+    /// Returns an already-resolved username without making a network call.
+    pub fn cached_name(&self, uuid: &str) -> Option<String> {
+        let uuid_no_dashes = uuid.replace('-', "");
+        self.cache.lock().unwrap().get(&uuid_no_dashes).map(|c| c.name.clone())
+    }
+
+    /// Resolves every not-yet-cached UUID in `uuids`, batching up to
+    /// [`BULK_BATCH_SIZE`] UUIDs per request with a single delay between
+    /// batches (instead of one sleep per UUID). A no-op if resolution was
+    /// disabled via `--no-mojang-api`.
+    pub fn resolve_all<'a, I: IntoIterator<Item = &'a str>>(&self, uuids: I) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut to_fetch: Vec<String> = {
+            let cache = self.cache.lock().unwrap();
+            let mut distinct: HashSet<String> = HashSet::new();
+            for uuid in uuids {
+                let normalized = uuid.replace('-', "");
+                if !cache.contains_key(&normalized) {
+                    distinct.insert(normalized);
+                }
+            }
+            distinct.into_iter().collect()
+        };
+        if to_fetch.is_empty() {
+            return;
+        }
+        to_fetch.sort(); // deterministic batching, easier to reason about in logs
+
+        let client = match Client::builder().timeout(self.timeout).build() {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("Failed to build HTTP client: {}", e);
+                return;
+            }
+        };
+
+        let batches: Vec<&[String]> = to_fetch.chunks(BULK_BATCH_SIZE).collect();
+        let batch_count = batches.len();
+        for (i, batch) in batches.into_iter().enumerate() {
+            match client.post(BULK_LOOKUP_URL).json(batch).send() {
+                Ok(response) if response.status().is_success() => {
+                    match response.json::<Vec<MinecraftProfile>>() {
+                        Ok(profiles) => {
+                            let fetched_at = SystemTime::now()
+                                .duration_since(UNIX_EPOCH)
+                                .map(|d| d.as_secs() as i64)
+                                .unwrap_or(0);
+                            let mut cache = self.cache.lock().unwrap();
+                            for profile in profiles {
+                                cache.insert(profile.id, CachedUsername { name: profile.name, fetched_at });
+                            }
+                        }
+                        Err(e) => eprintln!("Failed to parse Mojang bulk lookup response: {}", e),
+                    }
+                }
+                Ok(response) => eprintln!("Mojang bulk lookup failed: HTTP {}", response.status()),
+                Err(e) => eprintln!("Mojang bulk lookup request failed: {}", e),
+            }
+
+            // Sleep between batches (not after every single UUID) to avoid an "HTTP 429 Too Many Requests":
+            if i + 1 < batch_count {
+                thread::sleep(Duration::from_millis(1000));
+            }
+        }
+    }
+}
@@ -0,0 +1,358 @@
+//! A small recursive-descent parser for the NBT ("Named Binary Tag") format
+//! used by Minecraft save files, cf. https://minecraft.wiki/w/NBT_format
+//!
+//! After gzip-decompression, an NBT file is a single named tag (almost
+//! always a Compound) whose payload is laid out as:
+//! `[1 byte tag type][2-byte big-endian u16 name length][name UTF-8 bytes][payload]`
+//! (tag type 0, End, has no name and no payload and is only ever used to
+//! terminate a Compound).
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use flate2::read::GzDecoder;
+
+use crate::NBTError;
+
+/// One node of a parsed NBT tree.
+/// Cf. https://minecraft.wiki/w/NBT_format#Specification
+#[derive(Debug, Clone)]
+pub enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    Compound(HashMap<String, NbtTag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    /// If `self` is a [`NbtTag::Compound`], looks up `name` among its direct children.
+    fn child(&self, name: &str) -> Option<&NbtTag> {
+        match self {
+            NbtTag::Compound(map) => map.get(name),
+            _ => None,
+        }
+    }
+
+}
+
+/// A cursor over the raw, decompressed bytes of an NBT file.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], NBTError> {
+        let end = self.pos.checked_add(n).ok_or_else(|| NBTError { msg: "NBT offset overflow".to_string() })?;
+        let slice = self.data.get(self.pos..end).ok_or_else(|| NBTError { msg: format!("unexpected end of NBT data at offset {}", self.pos) })?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, NBTError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i8(&mut self) -> Result<i8, NBTError> {
+        Ok(self.u8()? as i8)
+    }
+
+    fn i16(&mut self) -> Result<i16, NBTError> {
+        Ok(i16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u16(&mut self) -> Result<u16, NBTError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, NBTError> {
+        Ok(i32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, NBTError> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, NBTError> {
+        Ok(f32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f64(&mut self) -> Result<f64, NBTError> {
+        Ok(f64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, NBTError> {
+        let len = self.u16()? as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|e| NBTError { msg: format!("invalid UTF-8 in NBT string: {}", e) })
+    }
+
+    /// Reads an array/list length prefix (a big-endian `i32`, per the NBT spec)
+    /// and validates it's non-negative before handing it back as a `usize`.
+    /// A negative length would otherwise sign-extend into a near-`usize::MAX`
+    /// value and blow up `Vec::with_capacity` with a capacity-overflow panic
+    /// on truncated/corrupted input, instead of surfacing as an `NBTError`.
+    fn len_prefix(&mut self) -> Result<usize, NBTError> {
+        let len = self.i32()?;
+        usize::try_from(len).map_err(|_e| NBTError { msg: format!("negative NBT array/list length: {}", len) })
+    }
+
+    /// Reads the payload of a tag whose type is already known (used both for
+    /// named tags and for the bare elements of a List).
+    fn payload(&mut self, tag_type: u8) -> Result<NbtTag, NBTError> {
+        Ok(match tag_type {
+            1 => NbtTag::Byte(self.i8()?),
+            2 => NbtTag::Short(self.i16()?),
+            3 => NbtTag::Int(self.i32()?),
+            4 => NbtTag::Long(self.i64()?),
+            5 => NbtTag::Float(self.f32()?),
+            6 => NbtTag::Double(self.f64()?),
+            7 => {
+                let len = self.len_prefix()?;
+                let mut bytes = Vec::with_capacity(len);
+                for _ in 0..len {
+                    bytes.push(self.i8()?);
+                }
+                NbtTag::ByteArray(bytes)
+            }
+            8 => NbtTag::String(self.string()?),
+            9 => {
+                let elem_type = self.u8()?;
+                let len = self.len_prefix()?;
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(self.payload(elem_type)?);
+                }
+                NbtTag::List(items)
+            }
+            10 => NbtTag::Compound(self.compound()?),
+            11 => {
+                let len = self.len_prefix()?;
+                let mut ints = Vec::with_capacity(len);
+                for _ in 0..len {
+                    ints.push(self.i32()?);
+                }
+                NbtTag::IntArray(ints)
+            }
+            12 => {
+                let len = self.len_prefix()?;
+                let mut longs = Vec::with_capacity(len);
+                for _ in 0..len {
+                    longs.push(self.i64()?);
+                }
+                NbtTag::LongArray(longs)
+            }
+            other => return Err(NBTError { msg: format!("unknown NBT tag type {}", other) }),
+        })
+    }
+
+    /// Reads the body of a Compound: a sequence of named tags terminated by an End tag.
+    fn compound(&mut self) -> Result<HashMap<String, NbtTag>, NBTError> {
+        let mut map = HashMap::new();
+        loop {
+            let tag_type = self.u8()?;
+            if tag_type == 0 {
+                break;
+            }
+            let name = self.string()?;
+            let tag = self.payload(tag_type)?;
+            map.insert(name, tag);
+        }
+        Ok(map)
+    }
+
+    /// Reads a full named tag (type + name + payload) as found at the top level of a file.
+    fn named_tag(&mut self) -> Result<(String, NbtTag), NBTError> {
+        let tag_type = self.u8()?;
+        if tag_type == 0 {
+            return Err(NBTError { msg: "expected a named tag, found a bare End tag".to_string() });
+        }
+        let name = self.string()?;
+        let tag = self.payload(tag_type)?;
+        Ok((name, tag))
+    }
+}
+
+/// A gzip-compressed NBT file, parsed into its full tree.
+/// Cf. https://minecraft.wiki/w/NBT_format
+pub struct NBTFile {
+    root: NbtTag,
+}
+
+impl NBTFile {
+    pub fn new(path: &Path) -> Result<Self, NBTError> {
+        let file = File::open(path).map_err(|_e| NBTError { msg: format!("Could not open file {:?}", path) })?;
+        let file = BufReader::new(file);
+        let mut file = GzDecoder::new(file);
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes).map_err(|_e| NBTError { msg: format!("Could not read file {:?}", path) })?;
+
+        let mut reader = Reader::new(&bytes);
+        let (_name, root) = reader.named_tag()?;
+        Ok(Self { root })
+    }
+
+    /// Descends into the tree following `path`, e.g. `["Data", "Player", "Pos"]`,
+    /// returning `None` if any step is missing or not a Compound.
+    pub fn get(&self, path: &[&str]) -> Option<&NbtTag> {
+        let mut current = &self.root;
+        for segment in path {
+            current = current.child(segment)?;
+        }
+        Some(current)
+    }
+
+    pub fn get_byte(&self, path: &[&str]) -> Result<i8, NBTError> {
+        match self.get(path) {
+            Some(NbtTag::Byte(v)) => Ok(*v),
+            Some(_) => Err(NBTError { msg: format!("{:?} is not a Byte", path) }),
+            None => Err(NBTError { msg: format!("{:?} not found", path) }),
+        }
+    }
+
+    pub fn get_int(&self, path: &[&str]) -> Result<i32, NBTError> {
+        match self.get(path) {
+            Some(NbtTag::Int(v)) => Ok(*v),
+            Some(_) => Err(NBTError { msg: format!("{:?} is not an Int", path) }),
+            None => Err(NBTError { msg: format!("{:?} not found", path) }),
+        }
+    }
+
+    pub fn get_long(&self, path: &[&str]) -> Result<i64, NBTError> {
+        match self.get(path) {
+            Some(NbtTag::Long(v)) => Ok(*v),
+            Some(_) => Err(NBTError { msg: format!("{:?} is not a Long", path) }),
+            None => Err(NBTError { msg: format!("{:?} not found", path) }),
+        }
+    }
+
+    pub fn get_float(&self, path: &[&str]) -> Result<f32, NBTError> {
+        match self.get(path) {
+            Some(NbtTag::Float(v)) => Ok(*v),
+            Some(_) => Err(NBTError { msg: format!("{:?} is not a Float", path) }),
+            None => Err(NBTError { msg: format!("{:?} not found", path) }),
+        }
+    }
+
+    /// Reads a 3-element `List` of `Double`s, as used by e.g. the `Pos` tag.
+    pub fn get_double_triplet(&self, path: &[&str]) -> Result<(f64, f64, f64), NBTError> {
+        match self.get(path) {
+            Some(NbtTag::List(items)) if items.len() == 3 => {
+                let mut doubles = [0.0f64; 3];
+                for (i, item) in items.iter().enumerate() {
+                    match item {
+                        NbtTag::Double(v) => doubles[i] = *v,
+                        _ => return Err(NBTError { msg: format!("{:?} contains a non-Double element", path) }),
+                    }
+                }
+                Ok((doubles[0], doubles[1], doubles[2]))
+            }
+            Some(_) => Err(NBTError { msg: format!("{:?} is not a 3-element List", path) }),
+            None => Err(NBTError { msg: format!("{:?} not found", path) }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes a named tag's header: `[tag_type][u16 name length][name bytes]`.
+    fn named_header(tag_type: u8, name: &str) -> Vec<u8> {
+        let mut buf = vec![tag_type];
+        buf.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        buf.extend_from_slice(name.as_bytes());
+        buf
+    }
+
+    /// Hand-builds the raw bytes of a Compound tag's body (its named children
+    /// followed by an End tag), mirroring the layout `Reader::compound` expects.
+    fn compound_body(children: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut buf: Vec<u8> = children.into_iter().flatten().collect();
+        buf.push(0); // End tag
+        buf
+    }
+
+    fn byte_tag(name: &str, v: i8) -> Vec<u8> {
+        let mut buf = named_header(1, name);
+        buf.push(v as u8);
+        buf
+    }
+
+    fn int_tag(name: &str, v: i32) -> Vec<u8> {
+        let mut buf = named_header(3, name);
+        buf.extend_from_slice(&v.to_be_bytes());
+        buf
+    }
+
+    fn double_list_tag(name: &str, values: &[f64]) -> Vec<u8> {
+        let mut buf = named_header(9, name);
+        buf.push(6); // element type: Double
+        buf.extend_from_slice(&(values.len() as i32).to_be_bytes());
+        for v in values {
+            buf.extend_from_slice(&v.to_be_bytes());
+        }
+        buf
+    }
+
+    fn compound_tag(name: &str, children: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut buf = named_header(10, name);
+        buf.extend_from_slice(&compound_body(children));
+        buf
+    }
+
+    fn parse_root(bytes: &[u8]) -> NbtTag {
+        let mut reader = Reader::new(bytes);
+        let (_name, root) = reader.named_tag().expect("well-formed test fixture should parse");
+        root
+    }
+
+    #[test]
+    fn get_resolves_nested_fields_by_explicit_path() {
+        let bytes = compound_tag("", vec![
+            compound_tag("Data", vec![int_tag("DataVersion", 100)]),
+            byte_tag("Flag", 1),
+        ]);
+        let file = NBTFile { root: parse_root(&bytes) };
+
+        assert_eq!(file.get_int(&["Data", "DataVersion"]).unwrap(), 100);
+        assert_eq!(file.get_byte(&["Flag"]).unwrap(), 1);
+        assert!(file.get(&["Data", "Missing"]).is_none());
+        // A field that only exists nested under "Data" must not be found unqualified,
+        // i.e. there's no accidental fallback to a depth-first search.
+        assert!(file.get(&["DataVersion"]).is_none());
+    }
+
+    #[test]
+    fn get_double_triplet_reads_a_three_element_double_list() {
+        let bytes = compound_tag("", vec![double_list_tag("Pos", &[1.0, 2.0, 3.0])]);
+        let file = NBTFile { root: parse_root(&bytes) };
+
+        assert_eq!(file.get_double_triplet(&["Pos"]).unwrap(), (1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn negative_array_length_is_rejected_instead_of_panicking() {
+        // IntArray tag with a length prefix of -1 (0xFFFFFFFF), no element data follows.
+        let mut bytes = named_header(11, "Bad");
+        bytes.extend_from_slice(&(-1i32).to_be_bytes());
+
+        let mut reader = Reader::new(&bytes);
+        let result = reader.named_tag();
+
+        assert!(result.is_err(), "expected a negative array length to be rejected, got {:?}", result);
+    }
+}